@@ -13,26 +13,379 @@
 use crate::synthesis::linear::pmh::synth_pmh;
 use ndarray::Array2;
 use numpy::PyReadonlyArray2;
+use pyo3::exceptions::PyValueError;
 use pyo3::{prelude::*, types::PyList};
 use qiskit_circuit::circuit_data::CircuitData;
 use qiskit_circuit::operations::{Param, StandardGate};
 use qiskit_circuit::Qubit;
 use smallvec::{smallvec, SmallVec};
+use std::collections::{HashSet, VecDeque};
 use std::f64::consts::PI;
 
 type Instruction = (StandardGate, SmallVec<[Param; 3]>, SmallVec<[Qubit; 2]>);
 
+/// A single phase-polynomial term, typed rather than carried around as a
+/// string. This is the internal replacement for the `"t"`/`"tdg"`/`"s"`/...
+/// string tags that used to flow all the way from the Python-facing list of
+/// `angles` down through [`InstructionIterator`] and the main Gray-Synth
+/// loop.
+///
+/// `Phase` covers both a literal rotation angle (`Param::Float`) and a
+/// symbolic, unbound angle (`Param::ParameterExpression`), so callers can
+/// synthesize a parametric `CircuitData` whose `PhaseGate`s are left free.
+#[derive(Clone, Debug)]
+enum PhaseAngle {
+    T,
+    Tdg,
+    S,
+    Sdg,
+    Z,
+    Phase(Param),
+}
+
+impl PhaseAngle {
+    /// Parses the legacy string encoding (`"t"`, `"tdg"`, `"s"`, `"sdg"`,
+    /// `"z"`, or a float literal, used as a `PhaseGate` angle in radians after
+    /// reduction modulo pi) that the Python API
+    /// has historically accepted. Kept only as a compatibility shim so the
+    /// public signature does not need to break.
+    fn from_legacy_str(angle: &str) -> PyResult<Self> {
+        Ok(match angle {
+            "t" => PhaseAngle::T,
+            "tdg" => PhaseAngle::Tdg,
+            "s" => PhaseAngle::S,
+            "sdg" => PhaseAngle::Sdg,
+            "z" => PhaseAngle::Z,
+            angle_in_pi => PhaseAngle::Phase(Param::Float(
+                angle_in_pi.parse::<f64>().map_err(|_| {
+                    PyValueError::new_err(format!("invalid angle string: {angle_in_pi}"))
+                })? % PI,
+            )),
+        })
+    }
+
+    /// Extracts a [`PhaseAngle`] from a Python object, accepting either the
+    /// legacy string tags or a `Param` (float or symbolic
+    /// `ParameterExpression`) directly.
+    fn from_py(angle: &Bound<PyAny>) -> PyResult<Self> {
+        if let Ok(angle_str) = angle.extract::<String>() {
+            return Self::from_legacy_str(&angle_str);
+        }
+        let param: Param = angle.extract()?;
+        Ok(PhaseAngle::Phase(param))
+    }
+
+    fn into_instruction(self, qubit: Qubit) -> Instruction {
+        match self {
+            PhaseAngle::T => (StandardGate::TGate, smallvec![], smallvec![qubit]),
+            PhaseAngle::Tdg => (StandardGate::TdgGate, smallvec![], smallvec![qubit]),
+            PhaseAngle::S => (StandardGate::SGate, smallvec![], smallvec![qubit]),
+            PhaseAngle::Sdg => (StandardGate::SdgGate, smallvec![], smallvec![qubit]),
+            PhaseAngle::Z => (StandardGate::ZGate, smallvec![], smallvec![qubit]),
+            PhaseAngle::Phase(param) => {
+                (StandardGate::PhaseGate, smallvec![param], smallvec![qubit])
+            }
+        }
+    }
+}
+
+/// The angle of a degree-2 phase-polynomial term `x_i * x_j`, synthesized as
+/// a two-qubit diagonal gate rather than decomposed into CX + single-qubit
+/// phase. Mirrors [`PhaseAngle`] one level up in gate arity.
+#[derive(Clone, Debug)]
+enum QuadraticPhaseAngle {
+    Z,
+    S,
+    Sdg,
+    Phase(Param),
+}
+
+impl QuadraticPhaseAngle {
+    fn from_legacy_str(angle: &str) -> PyResult<Self> {
+        Ok(match angle {
+            "z" => QuadraticPhaseAngle::Z,
+            "s" => QuadraticPhaseAngle::S,
+            "sdg" => QuadraticPhaseAngle::Sdg,
+            angle_in_pi => QuadraticPhaseAngle::Phase(Param::Float(
+                angle_in_pi.parse::<f64>().map_err(|_| {
+                    PyValueError::new_err(format!("invalid angle string: {angle_in_pi}"))
+                })? % PI,
+            )),
+        })
+    }
+
+    fn from_py(angle: &Bound<PyAny>) -> PyResult<Self> {
+        if let Ok(angle_str) = angle.extract::<String>() {
+            return Self::from_legacy_str(&angle_str);
+        }
+        let param: Param = angle.extract()?;
+        Ok(QuadraticPhaseAngle::Phase(param))
+    }
+
+    fn into_instruction(self, qubit_a: Qubit, qubit_b: Qubit) -> Instruction {
+        let qubits = smallvec![qubit_a, qubit_b];
+        match self {
+            QuadraticPhaseAngle::Z => (StandardGate::CZGate, smallvec![], qubits),
+            QuadraticPhaseAngle::S => (StandardGate::CSGate, smallvec![], qubits),
+            QuadraticPhaseAngle::Sdg => (StandardGate::CSdgGate, smallvec![], qubits),
+            QuadraticPhaseAngle::Phase(param) => {
+                (StandardGate::CPhaseGate, smallvec![param], qubits)
+            }
+        }
+    }
+}
+
+/// Parses the Python-facing `quadratic_terms` argument: a list of
+/// `(i, j, angle)` triples, one per degree-2 phase-polynomial term `x_i *
+/// x_j`, where `angle` follows the same legacy-string-or-`Param` convention
+/// as the linear `angles` argument.
+fn parse_quadratic_terms(
+    quadratic_terms: Option<&Bound<PyList>>,
+) -> PyResult<Vec<(usize, usize, QuadraticPhaseAngle)>> {
+    let Some(quadratic_terms) = quadratic_terms else {
+        return Ok(vec![]);
+    };
+    quadratic_terms
+        .iter()
+        .map(|term| {
+            let (i, j, angle): (usize, usize, Bound<PyAny>) = term.extract()?;
+            Ok((i, j, QuadraticPhaseAngle::from_py(&angle)?))
+        })
+        .collect()
+}
+
+/// Emits a CZ/CPhase/CS/CSdg layer for `terms`, one gate per degree-2 term,
+/// assuming all-to-all connectivity.
+fn emit_quadratic_layer(
+    instructions: &mut Vec<Instruction>,
+    terms: Vec<(usize, usize, QuadraticPhaseAngle)>,
+) {
+    for (i, j, angle) in terms {
+        instructions.push(angle.into_instruction(Qubit(i as u32), Qubit(j as u32)));
+    }
+}
+
+/// Emits a CZ/CPhase/CS/CSdg layer for `terms`, routing each term's two-qubit
+/// diagonal gate through the coupling graph when its qubits are not adjacent:
+/// a chain of nearest-neighbor SWAPs (three CX each) walks one endpoint along
+/// a shortest path until it sits next to the other, the diagonal gate is
+/// applied, and the same SWAP chain is reversed to restore every qubit it
+/// passed through. This is the line-depth fallback construction -- O(n) CX
+/// depth per term -- for coupling-constrained diagonal synthesis.
+fn emit_quadratic_layer_coupling(
+    instructions: &mut Vec<Instruction>,
+    adjacency: &[HashSet<usize>],
+    terms: Vec<(usize, usize, QuadraticPhaseAngle)>,
+) {
+    for (i, j, angle) in terms {
+        if adjacency[i].contains(&j) {
+            instructions.push(angle.into_instruction(Qubit(i as u32), Qubit(j as u32)));
+            continue;
+        }
+
+        let path = shortest_path(adjacency, i, j, &HashSet::new())
+            .expect("coupling map must be connected to synthesize a two-qubit diagonal gate");
+        let swap_hops = &path[..path.len() - 1];
+
+        for hop in swap_hops.windows(2) {
+            emit_swap(instructions, hop[0], hop[1]);
+        }
+        let neighbor_of_j = *swap_hops.last().unwrap();
+        instructions.push(angle.into_instruction(Qubit(neighbor_of_j as u32), Qubit(j as u32)));
+        for hop in swap_hops.windows(2).rev() {
+            emit_swap(instructions, hop[0], hop[1]);
+        }
+    }
+}
+
+/// Emits a SWAP between adjacent qubits `a` and `b` as the standard
+/// three-CX decomposition.
+fn emit_swap(instructions: &mut Vec<Instruction>, a: usize, b: usize) {
+    let (qa, qb) = (Qubit(a as u32), Qubit(b as u32));
+    instructions.push((StandardGate::CXGate, smallvec![], smallvec![qa, qb]));
+    instructions.push((StandardGate::CXGate, smallvec![], smallvec![qb, qa]));
+    instructions.push((StandardGate::CXGate, smallvec![], smallvec![qa, qb]));
+}
+
+impl PhaseAngle {
+    /// The angle this term implements, or `None` for a symbolic
+    /// (unbound `ParameterExpression`) phase -- [`verify_phase_polynomial`]
+    /// cannot check those numerically, so it skips them.
+    fn numeric_value(&self) -> Option<f64> {
+        match self {
+            PhaseAngle::T => Some(PI / 4.0),
+            PhaseAngle::Tdg => Some(-PI / 4.0),
+            PhaseAngle::S => Some(PI / 2.0),
+            PhaseAngle::Sdg => Some(-PI / 2.0),
+            PhaseAngle::Z => Some(PI),
+            PhaseAngle::Phase(Param::Float(angle)) => Some(*angle),
+            PhaseAngle::Phase(_) => None,
+        }
+    }
+}
+
+impl QuadraticPhaseAngle {
+    fn numeric_value(&self) -> Option<f64> {
+        match self {
+            QuadraticPhaseAngle::Z => Some(PI),
+            QuadraticPhaseAngle::S => Some(PI / 2.0),
+            QuadraticPhaseAngle::Sdg => Some(-PI / 2.0),
+            QuadraticPhaseAngle::Phase(Param::Float(angle)) => Some(*angle),
+            QuadraticPhaseAngle::Phase(_) => None,
+        }
+    }
+}
+
+/// The angle a single-qubit phase-type `StandardGate` implements, mirroring
+/// [`PhaseAngle::numeric_value`] for an already-emitted instruction.
+fn emitted_phase_angle(gate: StandardGate, params: &[Param]) -> Option<f64> {
+    phase_angle_of(gate, params)
+}
+
+/// The angle a two-qubit diagonal `StandardGate` implements (the quadratic
+/// counterpart of [`emitted_phase_angle`]).
+fn emitted_quadratic_angle(gate: StandardGate, params: &[Param]) -> Option<f64> {
+    match gate {
+        StandardGate::CZGate => Some(PI),
+        StandardGate::CSGate => Some(PI / 2.0),
+        StandardGate::CSdgGate => Some(-PI / 2.0),
+        StandardGate::CPhaseGate => match params.first() {
+            Some(Param::Float(angle)) => Some(*angle),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn angles_close(a: f64, b: f64) -> bool {
+    let two_pi = 2.0 * PI;
+    let diff = (a - b).rem_euclid(two_pi);
+    diff < ANGLE_TOL || (two_pi - diff) < ANGLE_TOL
+}
+
+/// Symbolically re-derives, over GF(2), the parity each emitted phase gate
+/// acts on and the overall linear transformation `instructions` implements,
+/// then checks both against what was asked for: `original_cnots`/
+/// `original_angles` (the degree-1 terms), `original_quadratic_terms` (the
+/// degree-2 terms), and `expected_state`, the linear map Gray-Synth tracked
+/// internally and handed to the final linear fixup (`synth_pmh`, or
+/// [`synth_linear_coupling`] in the coupling-constrained variant).
+///
+/// This is an O(gates * qubits) check: a running parity vector per qubit is
+/// maintained, XORing the target row by the control row on every `CXGate`,
+/// and the parity each phase/diagonal gate acts on is read off directly
+/// rather than requiring an exponential unitary comparison. Terms whose
+/// angle is a symbolic `ParameterExpression` are skipped, since their value
+/// cannot be compared numerically. Returns a `PyValueError` identifying the
+/// first mismatch found.
+fn verify_phase_polynomial(
+    instructions: &[Instruction],
+    num_qubits: usize,
+    original_cnots: &Array2<u8>,
+    original_angles: &[PhaseAngle],
+    original_quadratic_terms: &[(usize, usize, QuadraticPhaseAngle)],
+    expected_state: &Array2<u8>,
+) -> PyResult<()> {
+    let mut parity = Array2::<u8>::eye(num_qubits);
+    let mut seen_linear: Vec<(Vec<u8>, f64)> = vec![];
+    let mut seen_quadratic: Vec<((Vec<u8>, Vec<u8>), f64)> = vec![];
+
+    for (gate, params, qubits) in instructions {
+        match qubits.as_slice() {
+            [control, target] if *gate == StandardGate::CXGate => {
+                let control_row = parity.row(control.0 as usize).to_owned();
+                for k in 0..parity.ncols() {
+                    parity[(target.0 as usize, k)] ^= control_row[k];
+                }
+            }
+            [qubit] => {
+                if let Some(angle) = emitted_phase_angle(*gate, params) {
+                    seen_linear.push((parity.row(qubit.0 as usize).to_vec(), angle));
+                }
+            }
+            [a, b] => {
+                if let Some(angle) = emitted_quadratic_angle(*gate, params) {
+                    seen_quadratic.push((
+                        (
+                            parity.row(a.0 as usize).to_vec(),
+                            parity.row(b.0 as usize).to_vec(),
+                        ),
+                        angle,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for row in 0..num_qubits {
+        if parity.row(row).to_vec() != expected_state.row(row).to_vec() {
+            return Err(PyValueError::new_err(format!(
+                "synth_cnot_phase_aam check failed: reconstructed parity of qubit {row} does \
+                 not match the linear transformation recorded during synthesis"
+            )));
+        }
+    }
+
+    for (col, angle) in original_cnots.columns().into_iter().zip(original_angles) {
+        let Some(angle) = angle.numeric_value() else {
+            continue;
+        };
+        let col = col.to_vec();
+        let position = seen_linear
+            .iter()
+            .position(|(parity, seen_angle)| *parity == col && angles_close(*seen_angle, angle));
+        match position {
+            Some(index) => {
+                seen_linear.remove(index);
+            }
+            None => {
+                return Err(PyValueError::new_err(format!(
+                    "synth_cnot_phase_aam check failed: no emitted phase gate reconstructs \
+                     parity {col:?} with angle {angle}"
+                )));
+            }
+        }
+    }
+
+    for (i, j, angle) in original_quadratic_terms {
+        let Some(angle) = angle.numeric_value() else {
+            continue;
+        };
+        let mut e_i = vec![0u8; num_qubits];
+        e_i[*i] = 1;
+        let mut e_j = vec![0u8; num_qubits];
+        e_j[*j] = 1;
+        let position = seen_quadratic.iter().position(|((a, b), seen_angle)| {
+            (*a == e_i && *b == e_j || *a == e_j && *b == e_i) && angles_close(*seen_angle, angle)
+        });
+        match position {
+            Some(index) => {
+                seen_quadratic.remove(index);
+            }
+            None => {
+                return Err(PyValueError::new_err(format!(
+                    "synth_cnot_phase_aam check failed: no emitted diagonal gate reconstructs \
+                     the quadratic term x_{i} * x_{j} with angle {angle}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 struct InstructionIterator {
     s_cpy: Array2<u8>,
     state_cpy: Array2<u8>,
-    rust_angles_cpy: Vec<String>,
+    rust_angles_cpy: Vec<PhaseAngle>,
     num_qubits: usize,
     qubit_idx: usize,
     index: usize,
 }
 
 impl InstructionIterator {
-    fn new(s_cpy: Array2<u8>, state_cpy: Array2<u8>, rust_angles_cpy: Vec<String>) -> Self {
+    fn new(s_cpy: Array2<u8>, state_cpy: Array2<u8>, rust_angles_cpy: Vec<PhaseAngle>) -> Self {
         let num_qubits = s_cpy.nrows();
         Self {
             s_cpy,
@@ -44,7 +397,7 @@ impl InstructionIterator {
         }
     }
 
-    fn current_state(&self) -> (Array2<u8>, Vec<String>) {
+    fn current_state(&self) -> (Array2<u8>, Vec<PhaseAngle>) {
         (self.s_cpy.clone(), self.rust_angles_cpy.clone())
     }
 }
@@ -67,39 +420,7 @@ impl Iterator for InstructionIterator {
                 self.index -= 1;
                 self.s_cpy.remove_index(numpy::ndarray::Axis(1), self.index);
                 let angle = self.rust_angles_cpy.remove(self.index);
-
-                gate_instr = Some(match angle.as_str() {
-                    "t" => (
-                        StandardGate::TGate,
-                        smallvec![],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                    "tgd" => (
-                        StandardGate::TdgGate,
-                        smallvec![],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                    "s" => (
-                        StandardGate::SGate,
-                        smallvec![],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                    "sdg" => (
-                        StandardGate::SdgGate,
-                        smallvec![],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                    "z" => (
-                        StandardGate::ZGate,
-                        smallvec![],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                    angles_in_pi => (
-                        StandardGate::PhaseGate,
-                        smallvec![Param::Float((angles_in_pi.parse::<f64>().ok()?) % PI)],
-                        smallvec![Qubit(self.qubit_idx as u32)],
-                    ),
-                });
+                gate_instr = Some(angle.into_instruction(Qubit(self.qubit_idx as u32)));
             }
             if gate_instr.is_none() {
                 self.next()
@@ -114,26 +435,217 @@ impl Iterator for InstructionIterator {
     }
 }
 
+/// Numerical tolerance used by the post-synthesis peephole pass when
+/// comparing accumulated phase angles, e.g. to recognize that a merged
+/// rotation is (close enough to) a multiple of pi/4.
+const ANGLE_TOL: f64 = 1e-10;
+
+/// Whether `gate` is a single-qubit phase-type gate
+/// (`T`/`Tdg`/`S`/`Sdg`/`Z`/`Phase`), whatever its angle.
+fn is_phase_gate(gate: StandardGate) -> bool {
+    matches!(
+        gate,
+        StandardGate::TGate
+            | StandardGate::TdgGate
+            | StandardGate::SGate
+            | StandardGate::SdgGate
+            | StandardGate::ZGate
+            | StandardGate::PhaseGate
+    )
+}
+
+/// Returns the rotation angle of a single-qubit phase-type gate
+/// (`T`/`Tdg`/`S`/`Sdg`/`Z`/`Phase`), or `None` for any other gate or for a
+/// `PhaseGate` whose angle is a symbolic, unbound `ParameterExpression` (those
+/// cannot be merged numerically by the peephole pass).
+fn phase_angle_of(gate: StandardGate, params: &[Param]) -> Option<f64> {
+    match gate {
+        StandardGate::TGate => Some(PI / 4.0),
+        StandardGate::TdgGate => Some(-PI / 4.0),
+        StandardGate::SGate => Some(PI / 2.0),
+        StandardGate::SdgGate => Some(-PI / 2.0),
+        StandardGate::ZGate => Some(PI),
+        StandardGate::PhaseGate => match params.first() {
+            Some(Param::Float(angle)) => Some(*angle),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collapses an accumulated phase angle to the minimal single gate that
+/// implements it, dropping the gate entirely when the angle is (close
+/// enough to) a multiple of 2*pi.
+fn phase_instruction_for_angle(angle: f64, qubit: Qubit) -> Option<Instruction> {
+    let mut angle = angle % (2.0 * PI);
+    if angle < 0.0 {
+        angle += 2.0 * PI;
+    }
+    let q = smallvec![qubit];
+    if angle < ANGLE_TOL || (2.0 * PI - angle) < ANGLE_TOL {
+        None
+    } else if (angle - PI / 4.0).abs() < ANGLE_TOL {
+        Some((StandardGate::TGate, smallvec![], q))
+    } else if (angle - PI / 2.0).abs() < ANGLE_TOL {
+        Some((StandardGate::SGate, smallvec![], q))
+    } else if (angle - PI).abs() < ANGLE_TOL {
+        Some((StandardGate::ZGate, smallvec![], q))
+    } else if (angle - 3.0 * PI / 2.0).abs() < ANGLE_TOL {
+        Some((StandardGate::SdgGate, smallvec![], q))
+    } else if (angle - 7.0 * PI / 4.0).abs() < ANGLE_TOL {
+        Some((StandardGate::TdgGate, smallvec![], q))
+    } else {
+        Some((StandardGate::PhaseGate, smallvec![Param::Float(angle)], q))
+    }
+}
+
+/// A peephole cleanup over the `instructions` Gray-Synth just built, run to a
+/// fixpoint. It merges consecutive same-qubit phase rotations, cancels
+/// adjacent `CXGate` pairs sharing the same (control, target), and commutes a
+/// phase gate past a `CXGate` on which its qubit is the control -- since such
+/// a gate is diagonal on the control, it commutes through trivially -- to
+/// expose cancellations and merges that are not textually adjacent yet. This
+/// mirrors the commutation rules the oxidized commutative-cancellation pass
+/// already relies on, and typically removes CNOTs Gray-Synth leaves behind at
+/// section boundaries without changing the unitary.
+fn cancel_redundant_gates(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let mut current = instructions;
+    loop {
+        let (next, changed) = cancel_redundant_gates_pass(current);
+        current = next;
+        if !changed {
+            return current;
+        }
+    }
+}
+
+fn cancel_redundant_gates_pass(instructions: Vec<Instruction>) -> (Vec<Instruction>, bool) {
+    let mut out: Vec<Instruction> = Vec::with_capacity(instructions.len());
+    let mut changed = false;
+
+    for (gate, params, qubits) in instructions {
+        if qubits.len() == 1 {
+            if let Some(new_angle) = phase_angle_of(gate, &params) {
+                let qubit = qubits[0];
+
+                // Commute past any trailing CX gates controlled by this qubit, looking
+                // for an earlier phase term on the same qubit to merge with.
+                let mut skipped_cx = vec![];
+                while let Some((last_gate, _, last_qubits)) = out.last() {
+                    if *last_gate == StandardGate::CXGate && last_qubits[0] == qubit {
+                        skipped_cx.push(out.pop().unwrap());
+                    } else {
+                        break;
+                    }
+                }
+
+                let merged = match out.last() {
+                    Some((last_gate, last_params, last_qubits))
+                        if last_qubits.as_slice() == [qubit] =>
+                    {
+                        phase_angle_of(*last_gate, last_params)
+                            .map(|prev_angle| prev_angle + new_angle)
+                    }
+                    _ => None,
+                };
+
+                if let Some(total_angle) = merged {
+                    out.pop();
+                    out.extend(skipped_cx.into_iter().rev());
+                    out.extend(phase_instruction_for_angle(total_angle, qubit));
+                    changed = true;
+                    continue;
+                }
+
+                out.extend(skipped_cx.into_iter().rev());
+                out.push((gate, params, qubits));
+                continue;
+            }
+        }
+
+        if gate == StandardGate::CXGate {
+            // Commute past any trailing phase gates controlled by this CX's control
+            // qubit, looking for an earlier identical CX to cancel against.
+            let control = qubits[0];
+            let mut skipped_phases = vec![];
+            while let Some((last_gate, _, last_qubits)) = out.last() {
+                if last_qubits.as_slice() == [control] && is_phase_gate(*last_gate) {
+                    skipped_phases.push(out.pop().unwrap());
+                } else {
+                    break;
+                }
+            }
+
+            if let Some((last_gate, _, last_qubits)) = out.last() {
+                if *last_gate == StandardGate::CXGate && *last_qubits == qubits {
+                    out.pop();
+                    out.extend(skipped_phases.into_iter().rev());
+                    changed = true;
+                    continue;
+                }
+            }
+
+            out.extend(skipped_phases.into_iter().rev());
+            out.push((gate, params, qubits));
+            continue;
+        }
+
+        out.push((gate, params, qubits));
+    }
+
+    (out, changed)
+}
+
 /// This function implements a Gray-code inspired algorithm of synthesizing a circuit
 /// over CNOT and phase-gates with minimal-CNOT for a given phase-polynomial.
 /// The algorithm is described as "Gray-Synth" algorithm in Algorithm-1, page 12
 /// of paper "https://arxiv.org/abs/1712.01859".
+///
+/// When `optimize` is set, [`cancel_redundant_gates`] is run as a peephole
+/// cleanup over the emitted instructions before they are handed to
+/// `CircuitData`.
+///
+/// `quadratic_terms`, when given, extends the phase polynomial being
+/// synthesized with degree-2 terms `x_i * x_j`: each is emitted as its own
+/// two-qubit diagonal gate (`CZGate`/`CSGate`/`CSdgGate`/`CPhaseGate`) via
+/// [`emit_quadratic_layer`] rather than decomposed into CX and single-qubit
+/// phase gates.
+///
+/// When `check` is set, [`verify_phase_polynomial`] symbolically
+/// reconstructs the parity of every emitted phase/diagonal gate and the
+/// overall linear transformation over GF(2) and asserts they match
+/// `(cnots, angles, quadratic_terms)`, raising a `ValueError` identifying the
+/// first mismatch rather than letting a synthesis bug produce a silently
+/// wrong circuit.
 #[pyfunction]
-#[pyo3(signature = (cnots, angles, section_size=2))]
+#[pyo3(signature = (cnots, angles, section_size=2, optimize=false, quadratic_terms=None, check=false))]
 pub fn synth_cnot_phase_aam(
     py: Python,
     cnots: PyReadonlyArray2<u8>,
     angles: &Bound<PyList>,
     section_size: Option<i64>,
+    optimize: bool,
+    quadratic_terms: Option<&Bound<PyList>>,
+    check: bool,
 ) -> PyResult<CircuitData> {
     let s = cnots.as_array().to_owned();
     let num_qubits = s.nrows();
     let mut instructions = vec![];
+    let quadratic_terms = parse_quadratic_terms(quadratic_terms)?;
+    let original_quadratic_terms = quadratic_terms.clone();
+
+    // Emitted first, while every wire still holds its original qubit's value
+    // unmixed -- a diagonal two-qubit gate needs exactly that, and it
+    // commutes freely with the (also diagonal) single-qubit phase gates the
+    // rest of this function emits, so placement among those is free.
+    emit_quadratic_layer(&mut instructions, quadratic_terms);
 
-    let rust_angles: Vec<String> = angles
+    let rust_angles: Vec<PhaseAngle> = angles
         .iter()
-        .filter_map(|data| data.extract::<String>().ok())
-        .collect();
+        .map(|angle| PhaseAngle::from_py(&angle))
+        .collect::<PyResult<_>>()?;
+    let original_cnots = s.clone();
+    let original_angles = rust_angles.clone();
     let mut state = Array2::<u8>::eye(num_qubits);
 
     let mut instr_iter = InstructionIterator::new(s.clone(), state.clone(), rust_angles);
@@ -177,40 +689,8 @@ pub fn synth_cnot_phase_aam(
                         while index < s_cpy.ncols() {
                             let icnot = s_cpy.column(index).to_vec();
                             if icnot == state.row(_ep).to_vec() {
-                                match rust_angles.remove(index) {
-                                    gate if gate == "t" => instructions.push((
-                                        StandardGate::TGate,
-                                        smallvec![],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                    gate if gate == "tdg" => instructions.push((
-                                        StandardGate::TdgGate,
-                                        smallvec![],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                    gate if gate == "s" => instructions.push((
-                                        StandardGate::SGate,
-                                        smallvec![],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                    gate if gate == "sdg" => instructions.push((
-                                        StandardGate::SdgGate,
-                                        smallvec![],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                    gate if gate == "z" => instructions.push((
-                                        StandardGate::ZGate,
-                                        smallvec![],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                    angles_in_pi => instructions.push((
-                                        StandardGate::PhaseGate,
-                                        smallvec![Param::Float(
-                                            (angles_in_pi.parse::<f64>()?) % PI
-                                        )],
-                                        smallvec![Qubit(_ep as u32)],
-                                    )),
-                                };
+                                let angle = rust_angles.remove(index);
+                                instructions.push(angle.into_instruction(Qubit(_ep as u32)));
                                 s_cpy.remove_index(numpy::ndarray::Axis(1), index);
                                 if index == s_cpy.ncols() {
                                     break;
@@ -326,11 +806,590 @@ pub fn synth_cnot_phase_aam(
         ));
     }
 
+    if check {
+        verify_phase_polynomial(
+            &instructions,
+            num_qubits,
+            &original_cnots,
+            &original_angles,
+            &original_quadratic_terms,
+            &state,
+        )?;
+    }
+
     let state_bool = state.mapv(|x| x != 0);
     let mut instrs = synth_pmh(state_bool, section_size)
         .into_iter()
         .rev()
         .collect();
     instructions.append(&mut instrs);
+    if optimize {
+        instructions = cancel_redundant_gates(instructions);
+    }
     CircuitData::from_standard_gates(py, num_qubits as u32, instructions, Param::Float(0.0))
-}
\ No newline at end of file
+}
+
+/// Adjacency list for a coupling map, built from an edge list of physical
+/// qubit pairs. Used by [`synth_cnot_phase_aam_coupling`] to restrict CX
+/// gates to qubits that are actually connected.
+fn coupling_adjacency(coupling: &Array2<u32>, num_qubits: usize) -> Vec<HashSet<usize>> {
+    let mut adjacency = vec![HashSet::new(); num_qubits];
+    for edge in coupling.rows() {
+        let (a, b) = (edge[0] as usize, edge[1] as usize);
+        adjacency[a].insert(b);
+        adjacency[b].insert(a);
+    }
+    adjacency
+}
+
+/// Finds a shortest path from `start` to `goal` in `adjacency`, restricted to
+/// `allowed` nodes when that restricted subgraph already connects the two
+/// endpoints. This lets a Steiner tree reuse qubits that earlier routing
+/// steps have already touched (`allowed`) before falling back to the full
+/// coupling graph.
+fn shortest_path(
+    adjacency: &[HashSet<usize>],
+    start: usize,
+    goal: usize,
+    allowed: &HashSet<usize>,
+) -> Option<Vec<usize>> {
+    let restricted = bfs_path(adjacency, start, goal, Some(allowed));
+    if restricted.is_some() {
+        return restricted;
+    }
+    bfs_path(adjacency, start, goal, None)
+}
+
+fn bfs_path(
+    adjacency: &[HashSet<usize>],
+    start: usize,
+    goal: usize,
+    allowed: Option<&HashSet<usize>>,
+) -> Option<Vec<usize>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut parent = vec![usize::MAX; adjacency.len()];
+
+    while let Some(node) = queue.pop_front() {
+        for &next in &adjacency[node] {
+            if visited.contains(&next) {
+                continue;
+            }
+            if let Some(allowed) = allowed {
+                if next != goal && !allowed.contains(&next) {
+                    continue;
+                }
+            }
+            visited.insert(next);
+            parent[next] = node;
+            if next == goal {
+                let mut path = vec![goal];
+                let mut cur = goal;
+                while cur != start {
+                    cur = parent[cur];
+                    path.push(cur);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// Consumes every phase term in `s_cpy`/`rust_angles` whose column now
+/// matches `qubit`'s row of `state`, emitting the corresponding phase gate on
+/// `qubit`. Factored out of [`apply_routed_cx_step`] so the swap-chain
+/// bookkeeping in [`apply_routed_swap_step`] -- which changes the row of
+/// *two* qubits at once -- can call it for each.
+fn consume_matching_phase(
+    qubit: usize,
+    instructions: &mut Vec<Instruction>,
+    state: &Array2<u8>,
+    s_cpy: &mut Array2<u8>,
+    rust_angles: &mut Vec<PhaseAngle>,
+) {
+    let mut index = 0_usize;
+    let mut swtch = true;
+    while index < s_cpy.ncols() {
+        let icnot = s_cpy.column(index).to_vec();
+        if icnot == state.row(qubit).to_vec() {
+            let angle = rust_angles.remove(index);
+            instructions.push(angle.into_instruction(Qubit(qubit as u32)));
+            s_cpy.remove_index(numpy::ndarray::Axis(1), index);
+            if index == s_cpy.ncols() {
+                break;
+            }
+            if index == 0 {
+                swtch = false;
+            } else {
+                index -= 1;
+            }
+        }
+        if swtch {
+            index += 1;
+        } else {
+            swtch = true;
+        }
+    }
+}
+
+/// Pushes the CX instruction for `(control, target)` and applies the same
+/// bookkeeping the unconstrained Gray-Synth loop applies for a directly
+/// emitted CX: the linear `state` matrix is updated and any phase term that
+/// now matches the target row is consumed. Used for an already-adjacent CX,
+/// and for the single hop of a routed CX that actually lands on `target` (see
+/// [`emit_coupling_constrained_cx`]). Unlike the direct case, this does not
+/// touch the outer branch-and-bound queue `q` -- that bookkeeping tracks the
+/// conceptual `(control, target)` action on the remaining phase-polynomial
+/// columns, not the physical qubits a route happens to pass through, so it is
+/// applied once by the caller for the whole routed CX rather than once per
+/// hop.
+fn apply_routed_cx_step(
+    control: usize,
+    target: usize,
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+    s_cpy: &mut Array2<u8>,
+    rust_angles: &mut Vec<PhaseAngle>,
+) {
+    instructions.push((
+        StandardGate::CXGate,
+        smallvec![],
+        smallvec![Qubit(control as u32), Qubit(target as u32)],
+    ));
+
+    for k in 0..state.ncols() {
+        state[(target, k)] ^= state[(control, k)];
+    }
+
+    consume_matching_phase(target, instructions, state, s_cpy, rust_angles);
+}
+
+/// Swaps `a` and `b` (the standard three-CX decomposition, as in
+/// [`emit_swap`]) and mirrors that physical swap in `state`'s bookkeeping: the
+/// two rows are exchanged, and any phase term that now matches either row is
+/// consumed, exactly as [`apply_routed_cx_step`] does for the single row a
+/// direct CX changes.
+fn apply_routed_swap_step(
+    a: usize,
+    b: usize,
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+    s_cpy: &mut Array2<u8>,
+    rust_angles: &mut Vec<PhaseAngle>,
+) {
+    emit_swap(instructions, a, b);
+
+    for k in 0..state.ncols() {
+        let tmp = state[(a, k)];
+        state[(a, k)] = state[(b, k)];
+        state[(b, k)] = tmp;
+    }
+
+    consume_matching_phase(a, instructions, state, s_cpy, rust_angles);
+    consume_matching_phase(b, instructions, state, s_cpy, rust_angles);
+}
+
+/// Emits a CX between `control` and `target`, routing it through the coupling
+/// graph when the two qubits are not adjacent.
+///
+/// A naive "sweep down, sweep back up" fan of CXs along the path does not
+/// implement a clean `CX(control, target)`: it leaves every waypoint's
+/// original parity XORed into `target` instead of just `control`'s. Instead,
+/// as in [`emit_quadratic_layer_coupling`], `control` is walked along a
+/// shortest path towards `target` via a chain of nearest-neighbor SWAPs (each
+/// [`apply_routed_swap_step`], three CX) until it sits next to `target`, the
+/// CX is applied there via [`apply_routed_cx_step`], and the same SWAP chain
+/// is reversed to restore every qubit it passed through (SWAP being its own
+/// inverse).
+#[allow(clippy::too_many_arguments)]
+fn emit_coupling_constrained_cx(
+    control: usize,
+    target: usize,
+    adjacency: &[HashSet<usize>],
+    involved: &mut HashSet<usize>,
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+    s_cpy: &mut Array2<u8>,
+    rust_angles: &mut Vec<PhaseAngle>,
+) {
+    involved.insert(control);
+    involved.insert(target);
+
+    if adjacency[control].contains(&target) {
+        apply_routed_cx_step(control, target, instructions, state, s_cpy, rust_angles);
+        return;
+    }
+
+    let path = shortest_path(adjacency, control, target, involved)
+        .expect("coupling map must be connected to route a CX between its qubits");
+    involved.extend(path.iter().copied());
+
+    let swap_hops = &path[..path.len() - 1];
+    for hop in swap_hops.windows(2) {
+        apply_routed_swap_step(hop[0], hop[1], instructions, state, s_cpy, rust_angles);
+    }
+    let neighbor_of_target = *swap_hops.last().unwrap();
+    apply_routed_cx_step(
+        neighbor_of_target,
+        target,
+        instructions,
+        state,
+        s_cpy,
+        rust_angles,
+    );
+    for hop in swap_hops.windows(2).rev() {
+        apply_routed_swap_step(hop[0], hop[1], instructions, state, s_cpy, rust_angles);
+    }
+}
+
+/// Applies the CX instruction for `(control, target)` to `state` alone, with
+/// none of the phase-term bookkeeping [`apply_routed_cx_step`] also does --
+/// used by [`synth_linear_coupling`], which runs after every phase term has
+/// already been consumed.
+fn apply_cx_to_state(
+    control: usize,
+    target: usize,
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+) {
+    instructions.push((
+        StandardGate::CXGate,
+        smallvec![],
+        smallvec![Qubit(control as u32), Qubit(target as u32)],
+    ));
+    for k in 0..state.ncols() {
+        state[(target, k)] ^= state[(control, k)];
+    }
+}
+
+/// Swaps rows `a` and `b` of `state` via the three-CX SWAP decomposition, the
+/// `state`-only counterpart of [`apply_routed_swap_step`].
+fn apply_swap_to_state(
+    a: usize,
+    b: usize,
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+) {
+    emit_swap(instructions, a, b);
+    for k in 0..state.ncols() {
+        let tmp = state[(a, k)];
+        state[(a, k)] = state[(b, k)];
+        state[(b, k)] = tmp;
+    }
+}
+
+/// `state`-only counterpart of [`emit_coupling_constrained_cx`]: applies a
+/// CX(`control`, `target`) to `state`, routing it through the coupling graph
+/// via the same walk-and-restore SWAP chain when the two qubits are not
+/// adjacent, but without any phase-term bookkeeping -- used by
+/// [`synth_linear_coupling`].
+fn emit_coupling_constrained_cx_linear(
+    control: usize,
+    target: usize,
+    adjacency: &[HashSet<usize>],
+    instructions: &mut Vec<Instruction>,
+    state: &mut Array2<u8>,
+) {
+    if adjacency[control].contains(&target) {
+        apply_cx_to_state(control, target, instructions, state);
+        return;
+    }
+
+    let path = shortest_path(adjacency, control, target, &HashSet::new())
+        .expect("coupling map must be connected to route a CX between its qubits");
+    let swap_hops = &path[..path.len() - 1];
+
+    for hop in swap_hops.windows(2) {
+        apply_swap_to_state(hop[0], hop[1], instructions, state);
+    }
+    let neighbor_of_target = *swap_hops.last().unwrap();
+    apply_cx_to_state(neighbor_of_target, target, instructions, state);
+    for hop in swap_hops.windows(2).rev() {
+        apply_swap_to_state(hop[0], hop[1], instructions, state);
+    }
+}
+
+/// Coupling-constrained replacement for the all-to-all `synth_pmh` fixup:
+/// reduces the residual linear map `state` (left over once every phase term
+/// has been consumed) to the identity via Gauss-Jordan elimination, routing
+/// every row operation through `adjacency` with
+/// [`emit_coupling_constrained_cx_linear`] so the emitted CXs are as
+/// coupling-respecting as the rest of [`synth_cnot_phase_aam_coupling`]'s
+/// output. Unlike `synth_pmh`'s column-splitting, this does not optimize CX
+/// count beyond routing -- it is the correctness-first Steiner-tree
+/// counterpart, analogous to how [`emit_coupling_constrained_cx`] replaces a
+/// direct CX elsewhere in this function.
+///
+/// Unlike `synth_pmh`'s output, the returned instructions are appended in
+/// order, not reversed: applied after a circuit whose linear map is `state`,
+/// the elimination sequence itself brings that map down to the identity.
+fn synth_linear_coupling(mut state: Array2<u8>, adjacency: &[HashSet<usize>]) -> Vec<Instruction> {
+    let num_qubits = state.nrows();
+    let mut instructions = vec![];
+
+    for col in 0..num_qubits {
+        if state[(col, col)] == 0 {
+            let pivot = (col + 1..num_qubits)
+                .find(|&row| state[(row, col)] == 1)
+                .expect("state matrix must be invertible over GF(2)");
+            emit_coupling_constrained_cx_linear(
+                pivot,
+                col,
+                adjacency,
+                &mut instructions,
+                &mut state,
+            );
+            emit_coupling_constrained_cx_linear(
+                col,
+                pivot,
+                adjacency,
+                &mut instructions,
+                &mut state,
+            );
+            emit_coupling_constrained_cx_linear(
+                pivot,
+                col,
+                adjacency,
+                &mut instructions,
+                &mut state,
+            );
+        }
+
+        for row in 0..num_qubits {
+            if row != col && state[(row, col)] == 1 {
+                emit_coupling_constrained_cx_linear(
+                    col,
+                    row,
+                    adjacency,
+                    &mut instructions,
+                    &mut state,
+                );
+            }
+        }
+    }
+
+    instructions
+}
+
+/// Coupling-map (or linear-nearest-neighbor) constrained variant of
+/// [`synth_cnot_phase_aam`].
+///
+/// Gray-Synth ordinarily emits CX gates between whatever qubit pair the
+/// search needs next, assuming all-to-all connectivity. Here every such CX
+/// is instead routed along the `coupling` graph: when the two qubits are
+/// adjacent it is emitted directly, otherwise it is synthesized via
+/// [`emit_coupling_constrained_cx`] along a Steiner tree that may reuse
+/// qubits earlier routing steps already touched. For a line-shaped
+/// `coupling` this reduces to nearest-neighbor CX only, matching the
+/// LNN-synthesis style used elsewhere for CZ-depth-on-a-line circuits.
+///
+/// As in [`synth_cnot_phase_aam`], setting `optimize` runs
+/// [`cancel_redundant_gates`] over the emitted instructions before they are
+/// handed to `CircuitData`.
+///
+/// `quadratic_terms` extends the phase polynomial with degree-2 terms `x_i *
+/// x_j` exactly as in [`synth_cnot_phase_aam`], except each is routed
+/// through `coupling` via [`emit_quadratic_layer_coupling`] when its two
+/// qubits are not adjacent.
+///
+/// `check`, as in [`synth_cnot_phase_aam`], runs [`verify_phase_polynomial`]
+/// before the final [`synth_linear_coupling`] fixup is appended.
+#[pyfunction]
+#[pyo3(
+    signature = (cnots, angles, coupling, section_size=2, optimize=false, quadratic_terms=None, check=false)
+)]
+pub fn synth_cnot_phase_aam_coupling(
+    py: Python,
+    cnots: PyReadonlyArray2<u8>,
+    angles: &Bound<PyList>,
+    coupling: PyReadonlyArray2<u32>,
+    section_size: Option<i64>,
+    optimize: bool,
+    quadratic_terms: Option<&Bound<PyList>>,
+    check: bool,
+) -> PyResult<CircuitData> {
+    // Unlike `synth_cnot_phase_aam`'s `synth_pmh` fixup, the coupling-constrained
+    // `synth_linear_coupling` fixup below does not split the state matrix into
+    // sections, so `section_size` has no effect here; it is kept only so the
+    // two functions' Python signatures stay aligned.
+    let _ = section_size;
+    let s = cnots.as_array().to_owned();
+    let num_qubits = s.nrows();
+    let adjacency = coupling_adjacency(&coupling.as_array().to_owned(), num_qubits);
+    let mut involved: HashSet<usize> = HashSet::new();
+    let mut instructions = vec![];
+    let quadratic_terms = parse_quadratic_terms(quadratic_terms)?;
+    let original_quadratic_terms = quadratic_terms.clone();
+
+    // Emitted first, while every wire still holds its original qubit's value
+    // unmixed -- see the analogous comment in `synth_cnot_phase_aam`.
+    emit_quadratic_layer_coupling(&mut instructions, &adjacency, quadratic_terms);
+
+    let rust_angles: Vec<PhaseAngle> = angles
+        .iter()
+        .map(|angle| PhaseAngle::from_py(&angle))
+        .collect::<PyResult<_>>()?;
+    let original_cnots = s.clone();
+    let original_angles = rust_angles.clone();
+    let mut state = Array2::<u8>::eye(num_qubits);
+
+    let mut instr_iter = InstructionIterator::new(s.clone(), state.clone(), rust_angles);
+
+    let new_iter = std::iter::from_fn(|| instr_iter.next());
+    let mut ins: Vec<Instruction> = new_iter.collect::<Vec<Instruction>>();
+    let (mut s_cpy, mut rust_angles) = instr_iter.current_state();
+
+    instructions.append(&mut ins);
+
+    let epsilon: usize = num_qubits;
+    let mut q = vec![(s, (0..num_qubits).collect::<Vec<usize>>(), epsilon)];
+
+    while !q.is_empty() {
+        let (mut _s, mut _i, mut _ep) = q.pop().unwrap();
+
+        if _s.is_empty() {
+            continue;
+        }
+
+        if _ep < num_qubits {
+            let mut condition = true;
+            while condition {
+                condition = false;
+
+                for _j in 0..num_qubits {
+                    if (_j != _ep) && (_s.row(_j).sum() as usize == _s.row(_j).len()) {
+                        condition = true;
+
+                        emit_coupling_constrained_cx(
+                            _j,
+                            _ep,
+                            &adjacency,
+                            &mut involved,
+                            &mut instructions,
+                            &mut state,
+                            &mut s_cpy,
+                            &mut rust_angles,
+                        );
+
+                        q.push((_s, _i, _ep));
+                        let mut unique_q = vec![];
+                        for data in q.into_iter() {
+                            if !unique_q.contains(&data) {
+                                unique_q.push(data);
+                            }
+                        }
+
+                        q = unique_q;
+
+                        for data in &mut q {
+                            let (ref mut _temp_s, _, _) = data;
+
+                            if _temp_s.is_empty() {
+                                continue;
+                            }
+
+                            for idx in 0.._temp_s.row(_j).len() {
+                                _temp_s[(_j, idx)] ^= _temp_s[(_ep, idx)];
+                            }
+                        }
+
+                        (_s, _i, _ep) = q.pop().unwrap();
+                    }
+                }
+            }
+        }
+
+        if _i.is_empty() {
+            continue;
+        }
+
+        let maxes: Vec<usize> = _s
+            .axis_iter(numpy::ndarray::Axis(0))
+            .map(|row| {
+                std::cmp::max(
+                    row.iter().filter(|&&x| x == 0).count(),
+                    row.iter().filter(|&&x| x == 1).count(),
+                )
+            })
+            .collect();
+
+        let maxes2: Vec<usize> = _i.iter().map(|&_i_idx| maxes[_i_idx]).collect();
+
+        let _temp_argmax = maxes2
+            .iter()
+            .enumerate()
+            .max_by(|(_, x), (_, y)| x.cmp(y))
+            .map(|(idx, _)| idx)
+            .unwrap();
+
+        let _j = _i[_temp_argmax];
+
+        let mut cnots0_t = vec![];
+        let mut cnots1_t = vec![];
+
+        let mut cnots0_t_shape = (0_usize, _s.column(0).len());
+        let mut cnots1_t_shape = (0_usize, 0_usize);
+        cnots1_t_shape.1 = cnots0_t_shape.1;
+        for cols in _s.columns() {
+            if cols[_j] == 0 {
+                cnots0_t_shape.0 += 1;
+                cnots0_t.append(&mut cols.to_vec());
+            } else if cols[_j] == 1 {
+                cnots1_t_shape.0 += 1;
+                cnots1_t.append(&mut cols.to_vec());
+            }
+        }
+
+        let cnots0 =
+            Array2::from_shape_vec((cnots0_t_shape.0, cnots0_t_shape.1), cnots0_t).unwrap();
+        let cnots1 =
+            Array2::from_shape_vec((cnots1_t_shape.0, cnots1_t_shape.1), cnots1_t).unwrap();
+
+        let cnots0 = cnots0.reversed_axes().to_owned();
+        let cnots1 = cnots1.reversed_axes().to_owned();
+
+        if _ep == num_qubits {
+            q.push((
+                cnots1,
+                _i.clone().into_iter().filter(|&x| x != _j).collect(),
+                _j,
+            ));
+        } else {
+            q.push((
+                cnots1,
+                _i.clone().into_iter().filter(|&x| x != _j).collect(),
+                _ep,
+            ));
+        }
+
+        q.push((
+            cnots0,
+            _i.clone().into_iter().filter(|&x| x != _j).collect(),
+            _ep,
+        ));
+    }
+
+    if check {
+        verify_phase_polynomial(
+            &instructions,
+            num_qubits,
+            &original_cnots,
+            &original_angles,
+            &original_quadratic_terms,
+            &state,
+        )?;
+    }
+
+    let mut instrs: Vec<Instruction> = synth_linear_coupling(state, &adjacency);
+    instructions.append(&mut instrs);
+    if optimize {
+        instructions = cancel_redundant_gates(instructions);
+    }
+    CircuitData::from_standard_gates(py, num_qubits as u32, instructions, Param::Float(0.0))
+}